@@ -1,10 +1,22 @@
-use std::{cmp::Ordering, collections::{BinaryHeap}};
+use std::{cmp::Ordering, collections::{BinaryHeap, HashMap, HashSet, VecDeque}};
 
 #[derive(Debug, Clone)]
 pub struct Task {
     pub id: u64,
     pub queued_at: u32,
     pub execution_duration: u32,
+    /// Scheduling priority in the Linux `nice` range `[-20, 19]`; lower is higher priority.
+    /// Only consulted by `FairScheduler`; other schedulers ignore it.
+    pub nice: i8,
+    /// Real-time priority level, higher runs first. Only consulted by `RealTimeScheduler`;
+    /// other schedulers ignore it.
+    pub priority: u8,
+    /// Identifies the type of work this task does. Only consulted by `BatchingScheduler`,
+    /// which coalesces same-`kind` tasks into a single batch to amortize setup cost.
+    pub kind: u32,
+    /// Ids of tasks that must finish before this one becomes runnable. Only consulted by
+    /// `DagScheduler`; other schedulers ignore it.
+    pub depends_on: Vec<u64>,
 }
 
 /// Used to order tasks by descending execution_duration
@@ -52,9 +64,151 @@ fn get_shortest_task_ind(tasks: &Vec<&Task>) -> Option<usize> {
     None
 }
 
+/// A single observable transition in a scheduler's timeline: a task being queued, started, or
+/// finished (and, where preemption is supported, preempted/resumed).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScheduleEvent {
+    pub time: u32,
+    pub task_id: u64,
+    pub kind: EventKind,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EventKind {
+    Queued,
+    Started,
+    Finished,
+    Preempted,
+    Resumed,
+}
+
 pub trait Scheduler<'a> {
     fn new(tasks: &'a[Task]) -> Self;
     fn execution_order(&mut self) -> Vec<u64>;
+
+    /// A structured, chronologically-ordered log of every queue/start/finish transition.
+    /// Every `Scheduler` impl in this crate overrides this; the default only exists so a new
+    /// impl compiles before it grows its own `timeline()` (and panics loudly, rather than
+    /// silently, if someone calls `metrics()` before adding one). `DagScheduler` is scheduled
+    /// over a DAG that can contain a cycle, so its `execution_order` is fallible and it can't
+    /// implement this trait as-is; it exposes its own `Result`-returning `timeline`/`metrics`
+    /// pair instead.
+    fn timeline(&mut self) -> Vec<ScheduleEvent> {
+        unimplemented!("timeline() is not yet implemented for this scheduler")
+    }
+
+    /// Per-task and aggregate scheduling statistics (turnaround, waiting, response, makespan).
+    /// Derived entirely from `timeline()`, so any scheduler that implements one gets this for
+    /// free.
+    fn metrics(&mut self) -> SchedulerMetrics {
+        metrics_from_timeline(self.timeline())
+    }
+}
+
+/// Turnaround, waiting, and response time for a single task, as defined in Silberschatz et al.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TaskMetrics {
+    pub task_id: u64,
+    // Time from being queued to finishing: completion_time - queued_at
+    pub turnaround: u32,
+    // Time spent queued but not running: turnaround - execution_duration
+    pub waiting: u32,
+    // Time from being queued to first running: first_start - queued_at
+    pub response: u32,
+}
+
+/// Per-task statistics for a run, plus the averages of each and the overall makespan.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SchedulerMetrics {
+    pub per_task: Vec<TaskMetrics>,
+    pub avg_turnaround: f64,
+    pub avg_waiting: f64,
+    pub avg_response: f64,
+    // Time at which the last task finished
+    pub makespan: u32,
+}
+
+/// Accumulates the pieces of `TaskMetrics` for a single task as its timeline events are seen.
+#[derive(Debug, Default)]
+struct TaskTimelineProgress {
+    queued_at: Option<u32>,
+    first_started: Option<u32>,
+    last_finished: Option<u32>,
+    // Total time spent actually running, accrued across Started/Resumed..Preempted/Finished
+    // pairs; for a scheduler without preemption this is just the one pair.
+    busy_time: u32,
+    run_start: Option<u32>,
+}
+
+/// Derives `SchedulerMetrics` purely from a `timeline()`'s events, so it works for any
+/// `Scheduler` implementation without needing direct access to the original tasks.
+/// Time complexity: O(n*log(n))
+fn metrics_from_timeline(mut events: Vec<ScheduleEvent>) -> SchedulerMetrics {
+    // TC: O(n*log(n))
+    events.sort_by_key(|event| event.time);
+
+    let mut by_task: HashMap<u64, TaskTimelineProgress> = HashMap::new();
+    let mut makespan = 0;
+
+    // TC: O(n)
+    for event in &events {
+        let progress = by_task.entry(event.task_id).or_default();
+
+        match event.kind {
+            EventKind::Queued => {
+                if progress.queued_at.is_none() {
+                    progress.queued_at = Some(event.time);
+                }
+            }
+            EventKind::Started | EventKind::Resumed => {
+                if progress.first_started.is_none() {
+                    progress.first_started = Some(event.time);
+                }
+                progress.run_start = Some(event.time);
+            }
+            EventKind::Finished | EventKind::Preempted => {
+                if let Some(run_start) = progress.run_start.take() {
+                    progress.busy_time += event.time - run_start;
+                }
+                if event.kind == EventKind::Finished {
+                    progress.last_finished = Some(event.time);
+                }
+            }
+        }
+
+        makespan = makespan.max(event.time);
+    }
+
+    // Sort by task id for a deterministic order, since HashMap iteration order isn't one.
+    // TC: O(n*log(n))
+    let mut task_ids: Vec<u64> = by_task.keys().copied().collect();
+    task_ids.sort_unstable();
+
+    let per_task: Vec<TaskMetrics> = task_ids.into_iter().map(|task_id| {
+        let progress = &by_task[&task_id];
+        // Okay to unwrap: every task in the timeline was queued, started, and finished
+        let queued_at = progress.queued_at.unwrap();
+        let first_started = progress.first_started.unwrap();
+        let last_finished = progress.last_finished.unwrap();
+
+        let turnaround = last_finished - queued_at;
+        TaskMetrics {
+            task_id,
+            turnaround,
+            waiting: turnaround - progress.busy_time,
+            response: first_started - queued_at,
+        }
+    }).collect();
+
+    // An empty task set is a valid (if unusual) input to every scheduler's `new`; guard against
+    // dividing by zero so `metrics()` on it reports 0.0 averages instead of NaN.
+    let count = per_task.len() as f64;
+    let avg = |total: f64| if count == 0.0 { 0.0 } else { total / count };
+    let avg_turnaround = avg(per_task.iter().map(|m| m.turnaround as f64).sum());
+    let avg_waiting = avg(per_task.iter().map(|m| m.waiting as f64).sum());
+    let avg_response = avg(per_task.iter().map(|m| m.response as f64).sum());
+
+    SchedulerMetrics { per_task, avg_turnaround, avg_waiting, avg_response, makespan }
 }
 
 pub struct NaiveScheduler<'a> {
@@ -168,6 +322,46 @@ impl<'a> Scheduler<'a> for NaiveScheduler<'a> {
 
         executed_ids
     }
+
+    // Time Complexity: O(n^2)
+    fn timeline(&mut self) -> Vec<ScheduleEvent> {
+        let mut events = Vec::new();
+
+        // TC: O(n^2)
+        while self.unfinished() /* TC: O(1) */ {
+            // A task is only fast-forwarded to (rather than pulled from current_queue) if
+            // current_queue has nothing runnable yet - that's also the moment it gets queued.
+            // TC: O(n)
+            let is_arrival = get_shortest_task_ind(&self.current_queue).is_none();
+            let start_time = if is_arrival {
+                // Okay to unwrap because unfinished() guarantees an unqueued task here
+                self.unqueued_tasks.last().unwrap().queued_at
+            } else {
+                self.current_time
+            };
+
+            // Okay to unwrap because unqueued_tasks.len() > 0
+            // TC: O(n)
+            let next_task = self.get_next_task().unwrap();
+
+            if is_arrival {
+                events.push(ScheduleEvent { time: next_task.queued_at, task_id: next_task.id, kind: EventKind::Queued });
+            }
+            events.push(ScheduleEvent { time: start_time, task_id: next_task.id, kind: EventKind::Started });
+            events.push(ScheduleEvent { time: self.current_time, task_id: next_task.id, kind: EventKind::Finished });
+
+            // Queue any tasks submitted during execution, recording when each arrived
+            // TC: O(n)
+            for queued_task in self.get_new_tasks() {
+                events.push(ScheduleEvent { time: queued_task.queued_at, task_id: queued_task.id, kind: EventKind::Queued });
+                self.current_queue.push(queued_task);
+            }
+        }
+
+        // TC: O(n*log(n))
+        events.sort_by_key(|event| event.time);
+        events
+    }
 }
 
 struct CleverScheduler<'a> {
@@ -184,12 +378,13 @@ impl<'a> CleverScheduler<'a> {
     }
 
     // Time complexity: O(n*log(n))
-    fn queue_tasks_submitted_before(&mut self, time: u32) {
+    fn queue_tasks_submitted_before(&mut self, time: u32) -> Vec<&'a Task> {
         // Index of first task to be popped = # of tasks not to pop
         // TC: O(log(n))
         let num_later_tasks = self.unqueued_tasks.partition_point(|&task| task.queued_at >= time);
         // Number of tasks to pop
         let num_new_tasks = self.unqueued_tasks.len() - num_later_tasks;
+        let mut new_tasks = Vec::with_capacity(num_new_tasks);
         // TC: O(n*log(n)) - I think?
         for _ in 0..num_new_tasks {
             // Okay to unwrap because we know we have enough tasks to pop
@@ -197,7 +392,9 @@ impl<'a> CleverScheduler<'a> {
             let task = self.unqueued_tasks.pop().unwrap();
             // TC: O(log(n))
             self.current_queue.push(TaskDurationDesc(task));
+            new_tasks.push(task);
         }
+        new_tasks
     }
 
     // Time complexity: O(log(n))
@@ -271,67 +468,1572 @@ impl<'a> Scheduler<'a> for CleverScheduler<'a> {
 
         executed_ids
     }
+
+    // Time Complexity: O(n*log(n))
+    fn timeline(&mut self) -> Vec<ScheduleEvent> {
+        let mut events = Vec::new();
+
+        // TC: O(n*log(n))
+        while self.unfinished() /* TC: O(1) */ {
+            // A task is only fast-forwarded to (rather than popped from current_queue) if
+            // current_queue is empty - that's also the moment it gets queued.
+            let is_arrival = self.current_queue.is_empty();
+            let start_time = if is_arrival {
+                // Okay to unwrap because unfinished() guarantees an unqueued task here
+                self.unqueued_tasks.last().unwrap().queued_at
+            } else {
+                self.current_time
+            };
+
+            // Okay to unwrap because the queue is not empty
+            // TC: O(log(n))
+            let next_task = self.get_next_task().unwrap();
+
+            if is_arrival {
+                events.push(ScheduleEvent { time: next_task.queued_at, task_id: next_task.id, kind: EventKind::Queued });
+            }
+            events.push(ScheduleEvent { time: start_time, task_id: next_task.id, kind: EventKind::Started });
+            events.push(ScheduleEvent { time: self.current_time, task_id: next_task.id, kind: EventKind::Finished });
+
+            // Queue any tasks submitted during execution, recording when each arrived
+            // TC: O(n*log(n))
+            for queued_task in self.queue_tasks_submitted_before(self.current_time) {
+                events.push(ScheduleEvent { time: queued_task.queued_at, task_id: queued_task.id, kind: EventKind::Queued });
+            }
+        }
+
+        // TC: O(n*log(n))
+        events.sort_by_key(|event| event.time);
+        events
+    }
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+/// Used to order tasks by ascending *remaining* duration, which changes over time as a task
+/// runs (unlike `TaskDurationDesc`, which orders by the fixed `execution_duration`).
+/// Inspired by std::cmp::Reverse - https://doc.rust-lang.org/src/core/cmp.rs.html#584
+#[derive(Debug)]
+struct TaskRemainingAsc<'a> {
+    task: &'a Task,
+    remaining: u32,
+}
 
-    #[test]
-    fn reverse_queue_order() {
-        let tasks = vec![
-            Task { id: 42, queued_at: 5, execution_duration: 3 },
-            Task { id: 43, queued_at: 2, execution_duration: 3 },
-            Task { id: 44, queued_at: 0, execution_duration: 2 },
-        ];
+impl<'a> PartialEq for TaskRemainingAsc<'a> {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.remaining == other.remaining
+    }
+}
 
-        let mut naive_scheduler = NaiveScheduler::new(&tasks);
-        let mut clever_scheduler = CleverScheduler::new(&tasks);
+impl<'a> Eq for TaskRemainingAsc<'a> {}
 
-        assert_eq!(naive_scheduler.execution_order(), vec![44, 43, 42]);
-        assert_eq!(clever_scheduler.execution_order(), vec![44, 43, 42]);
+impl<'a> PartialOrd for TaskRemainingAsc<'a> {
+    #[inline]
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        other.remaining.partial_cmp(&self.remaining)
     }
+}
 
-    #[test]
-    fn accepts_slice_arg() {
-        let tasks = vec![
-            Task { id: 42, queued_at: 5, execution_duration: 3 },
-            Task { id: 43, queued_at: 2, execution_duration: 3 },
-            Task { id: 44, queued_at: 0, execution_duration: 2 },
-        ];
+impl<'a> Ord for TaskRemainingAsc<'a> {
+    #[inline]
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.remaining.cmp(&self.remaining)
+    }
+}
 
-        let mut naive_scheduler = NaiveScheduler::new(tasks.as_slice());
-        let mut clever_scheduler = CleverScheduler::new(&tasks);
+/// Event-driven preemptive shortest-remaining-time-first scheduler, modeled loosely on the
+/// preemption logic in the Linux RT/CFS schedulers: whenever a new task arrives, it's compared
+/// against whatever is currently running, and the one with less remaining work keeps the CPU.
+pub struct PreemptiveScheduler<'a> {
+    pub current_time: u32,
+    // Tasks that have not yet been queued, sorted reverse-chronologically for easy popping
+    unqueued_tasks: Vec<&'a Task>,
+    // Tasks that are queued and not currently running, keyed by remaining duration
+    ready_heap: BinaryHeap<TaskRemainingAsc<'a>>,
+    // The task currently occupying the CPU, if any
+    running: Option<TaskRemainingAsc<'a>>,
+}
 
-        assert_eq!(naive_scheduler.execution_order(), vec![44, 43, 42]);
-        assert_eq!(clever_scheduler.execution_order(), vec![44, 43, 42]);
+impl<'a> PreemptiveScheduler<'a> {
+    /// Time complexity: O(1)
+    fn unfinished(&self) -> bool {
+        self.running.is_some() || !self.ready_heap.is_empty() || !self.unqueued_tasks.is_empty()
     }
 
+    /// Queue every unqueued task whose `queued_at` has arrived by `self.current_time`.
+    /// Time complexity: O(k*log(n)) where k is the number of tasks newly queued
+    fn queue_arrivals(&mut self) {
+        while let Some(&task) = self.unqueued_tasks.last() {
+            if task.queued_at <= self.current_time {
+                self.unqueued_tasks.pop();
+                self.ready_heap.push(TaskRemainingAsc { task, remaining: task.execution_duration });
+            } else {
+                break;
+            }
+        }
+    }
 
-    // TODO: if two tasks are available with same duration, take the one queued first
+    /// The time of the next event: either the running task finishing, or the next arrival.
+    /// Time complexity: O(1)
+    fn next_event_time(&self) -> Option<u32> {
+        let finish_time = self.running.as_ref().map(|r| self.current_time + r.remaining);
+        let arrival_time = self.unqueued_tasks.last().map(|t| t.queued_at);
 
-    #[test]
-    fn two_items_queued_at_once() {
-        // 0: #42 is queued
-        // 0: #42 is started
-        // 1: #43 is queued
-        // 2: #44 is queued
-        // 3: #42 is finished
-        // 3: #44 is started (it is queued and has a lower execution_duration than #43)
-        // 5: #44 is finished
-        // 5: #43 is started
-        // 8: #43 is finished
+        match (finish_time, arrival_time) {
+            (Some(f), Some(a)) => Some(f.min(a)),
+            (Some(f), None) => Some(f),
+            (None, Some(a)) => Some(a),
+            (None, None) => None,
+        }
+    }
+}
 
-        let tasks = vec![
-            Task { id: 42, queued_at: 0, execution_duration: 3 },
-            Task { id: 43, queued_at: 1, execution_duration: 3 },
-            Task { id: 44, queued_at: 2, execution_duration: 2 },
-        ];
+impl<'a> Scheduler<'a> for PreemptiveScheduler<'a> {
+    /// Time complexity: O(n*log(n))
+    fn new(tasks: &'a [Task]) -> Self {
+        // TC: O(n)
+        let mut unqueued_tasks: Vec<&Task> = tasks.iter().collect();
+        // Sort unqueued tasks in reverse-chronological queue time for easy popping
+        // TC: O(n*log(n))
+        unqueued_tasks.sort_unstable_by(|&a, &b| b.queued_at.partial_cmp(&a.queued_at).unwrap());
 
-        let mut naive_scheduler = NaiveScheduler::new(&tasks);
-        let mut clever_scheduler = CleverScheduler::new(&tasks);
+        Self {
+            current_time: 0,
+            unqueued_tasks,
+            ready_heap: BinaryHeap::new(),
+            running: None,
+        }
+    }
 
-        assert_eq!(naive_scheduler.execution_order(), vec![42, 44, 43]);
-        assert_eq!(clever_scheduler.execution_order(), vec![42, 44, 43]);
+    // Time Complexity: O(n^2*log(n)) worst case (up to n preemptions per task)
+    fn execution_order(&mut self) -> Vec<u64> {
+        let mut executed_ids = Vec::<u64>::new();
+
+        while self.unfinished() {
+            // Jump straight to the next interesting event, since nothing changes in between
+            // Okay to unwrap because unfinished() guarantees a running task or an arrival
+            let event_time = self.next_event_time().unwrap();
+            let delta = event_time - self.current_time;
+            self.current_time = event_time;
+
+            // Charge the elapsed time against whatever was running
+            if let Some(running) = self.running.as_mut() {
+                running.remaining -= delta;
+            }
+
+            // Queue anything that arrived at or before the new current_time
+            self.queue_arrivals();
+
+            // If the running task just finished, record it and free up the CPU
+            if matches!(&self.running, Some(r) if r.remaining == 0) {
+                executed_ids.push(self.running.take().unwrap().task.id);
+            }
+
+            // Put the (possibly preempted) running task back into contention
+            if let Some(running) = self.running.take() {
+                self.ready_heap.push(running);
+            }
+
+            // Pick whichever runnable task now has the least remaining work
+            self.running = self.ready_heap.pop();
+        }
+
+        executed_ids
+    }
+
+    // Time Complexity: O(n^2*log(n)) worst case, same shape as execution_order
+    fn timeline(&mut self) -> Vec<ScheduleEvent> {
+        let mut events = Vec::new();
+
+        while self.unfinished() {
+            // Jump straight to the next interesting event, since nothing changes in between
+            // Okay to unwrap because unfinished() guarantees a running task or an arrival
+            let event_time = self.next_event_time().unwrap();
+            let delta = event_time - self.current_time;
+            self.current_time = event_time;
+
+            // Charge the elapsed time against whatever was running
+            if let Some(running) = self.running.as_mut() {
+                running.remaining -= delta;
+            }
+
+            // Queue anything that arrived at or before the new current_time
+            while let Some(&task) = self.unqueued_tasks.last() {
+                if task.queued_at <= self.current_time {
+                    self.unqueued_tasks.pop();
+                    events.push(ScheduleEvent { time: task.queued_at, task_id: task.id, kind: EventKind::Queued });
+                    self.ready_heap.push(TaskRemainingAsc { task, remaining: task.execution_duration });
+                } else {
+                    break;
+                }
+            }
+
+            // If the running task just finished, record it and free up the CPU
+            if matches!(&self.running, Some(r) if r.remaining == 0) {
+                let finished = self.running.take().unwrap();
+                events.push(ScheduleEvent { time: self.current_time, task_id: finished.task.id, kind: EventKind::Finished });
+            }
+
+            // Otherwise, put the running task back into contention; it's only preempted if a
+            // different task wins the CPU below
+            let mut contender = None;
+            if let Some(running) = self.running.take() {
+                contender = Some(running.task.id);
+                self.ready_heap.push(running);
+            }
+
+            // Pick whichever runnable task now has the least remaining work. It's a fresh start
+            // only the first time we see it (remaining == its full execution_duration); every
+            // other time it's resuming after a preemption.
+            self.running = self.ready_heap.pop();
+            if let Some(running) = &self.running {
+                if contender != Some(running.task.id) {
+                    if let Some(preempted_id) = contender {
+                        events.push(ScheduleEvent { time: self.current_time, task_id: preempted_id, kind: EventKind::Preempted });
+                    }
+                    let kind = if running.remaining == running.task.execution_duration {
+                        EventKind::Started
+                    } else {
+                        EventKind::Resumed
+                    };
+                    events.push(ScheduleEvent { time: self.current_time, task_id: running.task.id, kind });
+                }
+            }
+        }
+
+        // TC: O(n*log(n))
+        events.sort_by_key(|event| event.time);
+        events
+    }
+}
+
+/// Maps a `nice` value in `[-20, 19]` to a CFS-style scheduling weight, mirroring the table
+/// used by Linux's `sched_prio_to_weight` (nice 0 -> weight 1024, each step ~x1.25).
+const NICE_WEIGHTS: [u32; 40] = [
+    // nice -20 ..= -1
+    88761, 71755, 56483, 46273, 36291,
+    29154, 23254, 18705, 14949, 11916,
+    9548, 7620, 6100, 4904, 3906,
+    3121, 2501, 1991, 1586, 1277,
+    // nice 0 ..= 19
+    1024, 820, 655, 526, 423,
+    335, 272, 215, 172, 137,
+    110, 87, 70, 56, 45,
+    36, 29, 23, 18, 15,
+];
+
+/// Out-of-range `nice` values (outside `[-20, 19]`) are clamped to the nearest valid bound
+/// rather than panicking, since `Task::nice` is a plain `i8` field with no constructor to
+/// validate it up front.
+/// Time complexity: O(1)
+fn nice_to_weight(nice: i8) -> u32 {
+    let nice = nice.clamp(-20, 19);
+    NICE_WEIGHTS[(nice as i16 + 20) as usize]
+}
+
+/// A runnable entry in `FairScheduler`'s runqueue, ordered ascending by `vruntime` (ties broken
+/// by id) so the task picked next is always the one that has received the least virtual time.
+#[derive(Debug)]
+struct FairEntry<'a> {
+    task: &'a Task,
+    weight: u32,
+    remaining: u32,
+    vruntime: u64,
+}
+
+impl<'a> PartialEq for FairEntry<'a> {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.vruntime == other.vruntime && self.task.id == other.task.id
+    }
+}
+
+impl<'a> Eq for FairEntry<'a> {}
+
+impl<'a> PartialOrd for FairEntry<'a> {
+    #[inline]
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<'a> Ord for FairEntry<'a> {
+    #[inline]
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.vruntime.cmp(&self.vruntime).then_with(|| other.task.id.cmp(&self.task.id))
+    }
+}
+
+/// Weighted fair scheduler modeled on Linux CFS: CPU time is handed out in quantized slices of
+/// length `quantum`, and the runnable task with the lowest `vruntime` always runs next. A
+/// task's `vruntime` accrues slower the higher its weight (i.e. the lower its `nice` value), so
+/// higher-priority tasks get proportionally more CPU without ever starving the rest.
+pub struct FairScheduler<'a> {
+    pub current_time: u32,
+    pub quantum: u32,
+    // Tasks that have not yet been queued, sorted reverse-chronologically for easy popping
+    unqueued_tasks: Vec<&'a Task>,
+    // Tasks that are queued and not currently running
+    ready_heap: BinaryHeap<FairEntry<'a>>,
+    // The lowest vruntime in the runqueue as of the end of the last slice, used to seed new
+    // arrivals fairly
+    min_vruntime: u64,
+}
+
+impl<'a> FairScheduler<'a> {
+    /// The default slice length used by the `Scheduler` trait constructor.
+    pub const DEFAULT_QUANTUM: u32 = 4;
+
+    /// Build a `FairScheduler` with an explicit slice length, since the `Scheduler` trait's
+    /// `new` has no room for one.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `quantum` is `0`, since a zero-length slice never lets a task make progress
+    /// and would loop forever.
+    /// Time complexity: O(n*log(n))
+    pub fn with_quantum(tasks: &'a [Task], quantum: u32) -> Self {
+        assert_ne!(quantum, 0, "quantum must be greater than 0");
+
+        // TC: O(n)
+        let mut unqueued_tasks: Vec<&Task> = tasks.iter().collect();
+        // Sort unqueued tasks in reverse-chronological queue time for easy popping
+        // TC: O(n*log(n))
+        unqueued_tasks.sort_unstable_by(|&a, &b| b.queued_at.partial_cmp(&a.queued_at).unwrap());
+
+        Self {
+            current_time: 0,
+            quantum,
+            unqueued_tasks,
+            ready_heap: BinaryHeap::new(),
+            min_vruntime: 0,
+        }
+    }
+
+    /// Queue every unqueued task whose `queued_at` has arrived by `self.current_time`, seeding
+    /// each one's `vruntime` at the current minimum so it can't unfairly dominate the CPU.
+    /// Time complexity: O(k*log(n)) where k is the number of tasks newly queued
+    fn queue_arrivals(&mut self) {
+        while let Some(&task) = self.unqueued_tasks.last() {
+            if task.queued_at <= self.current_time {
+                self.unqueued_tasks.pop();
+                self.ready_heap.push(FairEntry {
+                    task,
+                    weight: nice_to_weight(task.nice),
+                    remaining: task.execution_duration,
+                    vruntime: self.min_vruntime,
+                });
+            } else {
+                break;
+            }
+        }
+    }
+
+    /// Time complexity: O(1)
+    fn unfinished(&self) -> bool {
+        !self.ready_heap.is_empty() || !self.unqueued_tasks.is_empty()
+    }
+}
+
+impl<'a> Scheduler<'a> for FairScheduler<'a> {
+    /// Uses `DEFAULT_QUANTUM`; call `FairScheduler::with_quantum` directly for a custom slice
+    /// length.
+    /// Time complexity: O(n*log(n))
+    fn new(tasks: &'a [Task]) -> Self {
+        Self::with_quantum(tasks, Self::DEFAULT_QUANTUM)
+    }
+
+    // Time Complexity: O(s*log(n)), where s is the number of slices: each task takes
+    // ceil(execution_duration / quantum) of them
+    fn execution_order(&mut self) -> Vec<u64> {
+        // Ids of tasks recorded in the order their slices ran (repeated ids mean repeated slices)
+        let mut executed_ids = Vec::<u64>::new();
+
+        while self.unfinished() {
+            // Fast-forward if nothing is runnable yet
+            if self.ready_heap.is_empty() {
+                // Okay to unwrap because unfinished() guarantees an unqueued task in this branch
+                let next_arrival = *self.unqueued_tasks.last().unwrap();
+                self.current_time = self.current_time.max(next_arrival.queued_at);
+                self.queue_arrivals();
+            }
+
+            // Pick the task with the least accumulated virtual runtime
+            // Okay to unwrap because ready_heap is non-empty at this point
+            let mut entry = self.ready_heap.pop().unwrap();
+            executed_ids.push(entry.task.id);
+
+            // Run for at most one quantum, or however much work remains
+            let delta = self.quantum.min(entry.remaining);
+            self.current_time += delta;
+            entry.remaining -= delta;
+            entry.vruntime += (delta as u64 * 1024) / entry.weight as u64;
+
+            // Queue any tasks that arrived during the slice
+            self.queue_arrivals();
+
+            if entry.remaining > 0 {
+                // Still has work left; go back in the runqueue to compete for the next slice
+                self.ready_heap.push(entry);
+            }
+
+            // Keep min_vruntime current so freshly-arriving tasks are seeded fairly
+            if let Some(min_entry) = self.ready_heap.peek() {
+                self.min_vruntime = min_entry.vruntime;
+            }
+        }
+
+        executed_ids
+    }
+
+    // Time Complexity: O(n*log(n)) per slice, same shape as execution_order
+    fn timeline(&mut self) -> Vec<ScheduleEvent> {
+        let mut events = Vec::new();
+        // Ids that have already had a slice, so later slices are Resumed rather than Started
+        let mut started: HashSet<u64> = HashSet::new();
+
+        while self.unfinished() {
+            // Fast-forward if nothing is runnable yet
+            if self.ready_heap.is_empty() {
+                // Okay to unwrap because unfinished() guarantees an unqueued task in this branch
+                let next_arrival = *self.unqueued_tasks.last().unwrap();
+                self.current_time = self.current_time.max(next_arrival.queued_at);
+            }
+
+            // Queue anything that's arrived by now, recording when each one was queued
+            while let Some(&task) = self.unqueued_tasks.last() {
+                if task.queued_at <= self.current_time {
+                    self.unqueued_tasks.pop();
+                    events.push(ScheduleEvent { time: task.queued_at, task_id: task.id, kind: EventKind::Queued });
+                    self.ready_heap.push(FairEntry {
+                        task,
+                        weight: nice_to_weight(task.nice),
+                        remaining: task.execution_duration,
+                        vruntime: self.min_vruntime,
+                    });
+                } else {
+                    break;
+                }
+            }
+
+            // Pick the task with the least accumulated virtual runtime
+            // Okay to unwrap because ready_heap is non-empty at this point
+            let mut entry = self.ready_heap.pop().unwrap();
+            let kind = if started.insert(entry.task.id) { EventKind::Started } else { EventKind::Resumed };
+            events.push(ScheduleEvent { time: self.current_time, task_id: entry.task.id, kind });
+
+            // Run for at most one quantum, or however much work remains
+            let delta = self.quantum.min(entry.remaining);
+            self.current_time += delta;
+            entry.remaining -= delta;
+            entry.vruntime += (delta as u64 * 1024) / entry.weight as u64;
+
+            // Queue any tasks that arrived during the slice
+            while let Some(&task) = self.unqueued_tasks.last() {
+                if task.queued_at <= self.current_time {
+                    self.unqueued_tasks.pop();
+                    events.push(ScheduleEvent { time: task.queued_at, task_id: task.id, kind: EventKind::Queued });
+                    self.ready_heap.push(FairEntry {
+                        task,
+                        weight: nice_to_weight(task.nice),
+                        remaining: task.execution_duration,
+                        vruntime: self.min_vruntime,
+                    });
+                } else {
+                    break;
+                }
+            }
+
+            if entry.remaining > 0 {
+                // Still has work left; the slice ran out, so this is a preemption
+                events.push(ScheduleEvent { time: self.current_time, task_id: entry.task.id, kind: EventKind::Preempted });
+                self.ready_heap.push(entry);
+            } else {
+                events.push(ScheduleEvent { time: self.current_time, task_id: entry.task.id, kind: EventKind::Finished });
+            }
+
+            // Keep min_vruntime current so freshly-arriving tasks are seeded fairly
+            if let Some(min_entry) = self.ready_heap.peek() {
+                self.min_vruntime = min_entry.vruntime;
+            }
+        }
+
+        // TC: O(n*log(n))
+        events.sort_by_key(|event| event.time);
+        events
+    }
+}
+
+/// Number of distinct priority levels, one per possible `u8` value.
+const NUM_PRIORITY_LEVELS: usize = u8::MAX as usize + 1;
+
+/// Priority-array real-time scheduler modeled on the priority-array design in Linux's
+/// `sched_rt.c`: the runqueue is a `Vec` of per-priority FIFOs plus a bitmap of which levels
+/// are non-empty, so picking the next task to run is `O(levels)` instead of a scan over every
+/// runnable task. Within a priority level, tasks run FIFO/round-robin; a higher `priority`
+/// value always preempts a lower one - including interrupting a task already running, the
+/// moment a higher-priority task arrives, not just at its next natural dispatch point.
+pub struct RealTimeScheduler<'a> {
+    pub current_time: u32,
+    // When Some, a running task is requeued at the tail of its level once this much time has
+    // elapsed (the `requeue_task_rt` behavior); when None, tasks run until finished or until a
+    // higher-priority task preempts them.
+    pub time_slice: Option<u32>,
+    // Tasks that have not yet been queued, sorted reverse-chronologically for easy popping
+    unqueued_tasks: Vec<&'a Task>,
+    // Runqueue indexed by priority level; each level is a FIFO of runnable tasks
+    runqueue: Vec<VecDeque<&'a Task>>,
+    // Bitmap marking which priority levels are non-empty, for O(levels) pick_next
+    active_levels: Vec<bool>,
+    // Work remaining per task id, since a task may be requeued multiple times before finishing
+    remaining: HashMap<u64, u32>,
+}
+
+impl<'a> RealTimeScheduler<'a> {
+    /// Build a `RealTimeScheduler` with an explicit time-slice, since the `Scheduler` trait's
+    /// `new` has no room for one.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `time_slice` is `Some(0)`, since a zero-length slice never lets a task make
+    /// progress and would requeue it forever.
+    /// Time complexity: O(n*log(n))
+    pub fn with_time_slice(tasks: &'a [Task], time_slice: Option<u32>) -> Self {
+        assert_ne!(time_slice, Some(0), "time_slice must be greater than 0, or None to run to completion");
+
+        // TC: O(n)
+        let mut unqueued_tasks: Vec<&Task> = tasks.iter().collect();
+        // Sort unqueued tasks in reverse-chronological queue time for easy popping
+        // TC: O(n*log(n))
+        unqueued_tasks.sort_unstable_by(|&a, &b| b.queued_at.partial_cmp(&a.queued_at).unwrap());
+
+        Self {
+            current_time: 0,
+            time_slice,
+            unqueued_tasks,
+            runqueue: (0..NUM_PRIORITY_LEVELS).map(|_| VecDeque::new()).collect(),
+            active_levels: vec![false; NUM_PRIORITY_LEVELS],
+            remaining: HashMap::new(),
+        }
+    }
+
+    /// Time complexity: O(levels)
+    fn unfinished(&self) -> bool {
+        !self.unqueued_tasks.is_empty() || self.active_levels.iter().any(|&active| active)
+    }
+
+    /// The highest non-empty priority level, if any.
+    /// Time complexity: O(levels)
+    fn pick_next_level(&self) -> Option<usize> {
+        self.active_levels.iter().rposition(|&active| active)
+    }
+
+    /// The time of the next event: either the running task hitting its time-slice limit or
+    /// finishing, or the next arrival - whichever comes first. Stopping at the earlier of the
+    /// two (rather than always running to slice/finish) is what lets a higher-priority arrival
+    /// preempt mid-run instead of only being noticed once the CPU next goes idle.
+    /// Time complexity: O(1)
+    fn next_event_time(&self, running: &Option<(usize, &'a Task, u32, u32)>) -> u32 {
+        let finish_or_slice_time = running.as_ref().map(|&(_, _, remaining, slice_elapsed)| {
+            let slice_left = match self.time_slice {
+                Some(slice) => slice.saturating_sub(slice_elapsed),
+                None => remaining,
+            };
+            self.current_time + slice_left.min(remaining)
+        });
+        let arrival_time = self.unqueued_tasks.last().map(|task| task.queued_at);
+
+        match (finish_or_slice_time, arrival_time) {
+            (Some(f), Some(a)) => f.min(a),
+            (Some(f), None) => f,
+            (None, Some(a)) => a,
+            // Callers only invoke this when unfinished() || running.is_some(), which guarantees
+            // at least one of the two above is Some.
+            (None, None) => unreachable!("next_event_time called with nothing left to schedule"),
+        }
+    }
+
+    /// Time complexity: O(1)
+    fn enqueue(&mut self, task: &'a Task) {
+        let level = task.priority as usize;
+        self.runqueue[level].push_back(task);
+        self.active_levels[level] = true;
+        self.remaining.entry(task.id).or_insert(task.execution_duration);
+    }
+
+    /// Queue every unqueued task whose `queued_at` has arrived by `self.current_time`.
+    /// Time complexity: O(k) where k is the number of tasks newly queued
+    fn queue_arrivals(&mut self) {
+        while let Some(&task) = self.unqueued_tasks.last() {
+            if task.queued_at <= self.current_time {
+                self.unqueued_tasks.pop();
+                self.enqueue(task);
+            } else {
+                break;
+            }
+        }
+    }
+}
+
+impl<'a> Scheduler<'a> for RealTimeScheduler<'a> {
+    /// Uses no time-slice (run-to-completion); call `RealTimeScheduler::with_time_slice`
+    /// directly for round-robin behavior within a priority level.
+    /// Time complexity: O(n*log(n))
+    fn new(tasks: &'a [Task]) -> Self {
+        Self::with_time_slice(tasks, None)
+    }
+
+    // Time Complexity: O((n + r) * levels), where r is the number of preemptions and
+    // time-slice requeues (one level scan per event). Event-driven so a higher-priority arrival can cut a run short, the same way
+    // `PreemptiveScheduler` reacts to a shorter arrival.
+    fn execution_order(&mut self) -> Vec<u64> {
+        let mut executed_ids = Vec::<u64>::new();
+        // The task presently holding the CPU, its priority level, remaining work, and how much
+        // of its current time-slice it has used so far.
+        let mut running: Option<(usize, &Task, u32, u32)> = None;
+
+        while self.unfinished() || running.is_some() {
+            // Jump straight to the next interesting event: either the running task hitting its
+            // slice limit or finishing, or the next arrival - whichever comes first, since a
+            // higher-priority arrival needs a chance to preempt before that point.
+            let event_time = self.next_event_time(&running);
+            let delta = event_time - self.current_time;
+            self.current_time = event_time;
+
+            if let Some((_, _, remaining, slice_elapsed)) = running.as_mut() {
+                *remaining -= delta;
+                *slice_elapsed += delta;
+            }
+
+            // Queue anything that arrived at or before the new current_time
+            self.queue_arrivals();
+
+            // A higher-priority arrival preempts immediately, even mid-slice. It goes back to
+            // the head of its own level rather than the tail, since it didn't get to finish its
+            // turn (unlike a plain time-slice expiry, which is a fair round-robin requeue).
+            if let Some((level, task, remaining, _)) = running {
+                if remaining > 0 && self.pick_next_level().is_some_and(|top| top > level) {
+                    self.runqueue[level].push_front(task);
+                    self.active_levels[level] = true;
+                    self.remaining.insert(task.id, remaining);
+                    running = None;
+                }
+            }
+
+            if let Some((level, task, remaining, slice_elapsed)) = running {
+                if remaining == 0 {
+                    executed_ids.push(task.id);
+                    self.remaining.remove(&task.id);
+                    running = None;
+                } else if matches!(self.time_slice, Some(slice) if slice_elapsed >= slice) {
+                    // Time slice exhausted with work left; requeue at the tail of its own level
+                    self.runqueue[level].push_back(task);
+                    self.active_levels[level] = true;
+                    self.remaining.insert(task.id, remaining);
+                    running = None;
+                } else {
+                    self.remaining.insert(task.id, remaining);
+                }
+            }
+
+            if running.is_none() {
+                if let Some(level) = self.pick_next_level() {
+                    let task = self.runqueue[level].pop_front().unwrap();
+                    if self.runqueue[level].is_empty() {
+                        self.active_levels[level] = false;
+                    }
+                    // Okay to unwrap because every runnable task has a remaining-time entry
+                    let remaining = *self.remaining.get(&task.id).unwrap();
+                    running = Some((level, task, remaining, 0));
+                }
+            }
+        }
+
+        executed_ids
+    }
+
+    // Time Complexity: O(n * levels) in the worst case, same shape as execution_order
+    fn timeline(&mut self) -> Vec<ScheduleEvent> {
+        let mut events = Vec::new();
+        // Ids that have already run once, so a later dispatch is Resumed rather than Started
+        let mut started: HashSet<u64> = HashSet::new();
+        let mut running: Option<(usize, &Task, u32, u32)> = None;
+
+        while self.unfinished() || running.is_some() {
+            let event_time = self.next_event_time(&running);
+            let delta = event_time - self.current_time;
+            self.current_time = event_time;
+
+            if let Some((_, _, remaining, slice_elapsed)) = running.as_mut() {
+                *remaining -= delta;
+                *slice_elapsed += delta;
+            }
+
+            // Queue anything that's arrived by now, recording when each one was queued
+            while let Some(&task) = self.unqueued_tasks.last() {
+                if task.queued_at <= self.current_time {
+                    self.unqueued_tasks.pop();
+                    events.push(ScheduleEvent { time: task.queued_at, task_id: task.id, kind: EventKind::Queued });
+                    self.enqueue(task);
+                } else {
+                    break;
+                }
+            }
+
+            if let Some((level, task, remaining, _)) = running {
+                if remaining > 0 && self.pick_next_level().is_some_and(|top| top > level) {
+                    events.push(ScheduleEvent { time: self.current_time, task_id: task.id, kind: EventKind::Preempted });
+                    self.runqueue[level].push_front(task);
+                    self.active_levels[level] = true;
+                    self.remaining.insert(task.id, remaining);
+                    running = None;
+                }
+            }
+
+            if let Some((level, task, remaining, slice_elapsed)) = running {
+                if remaining == 0 {
+                    events.push(ScheduleEvent { time: self.current_time, task_id: task.id, kind: EventKind::Finished });
+                    self.remaining.remove(&task.id);
+                    running = None;
+                } else if matches!(self.time_slice, Some(slice) if slice_elapsed >= slice) {
+                    events.push(ScheduleEvent { time: self.current_time, task_id: task.id, kind: EventKind::Preempted });
+                    self.runqueue[level].push_back(task);
+                    self.active_levels[level] = true;
+                    self.remaining.insert(task.id, remaining);
+                    running = None;
+                } else {
+                    self.remaining.insert(task.id, remaining);
+                }
+            }
+
+            if running.is_none() {
+                if let Some(level) = self.pick_next_level() {
+                    let task = self.runqueue[level].pop_front().unwrap();
+                    if self.runqueue[level].is_empty() {
+                        self.active_levels[level] = false;
+                    }
+                    let kind = if started.insert(task.id) { EventKind::Started } else { EventKind::Resumed };
+                    events.push(ScheduleEvent { time: self.current_time, task_id: task.id, kind });
+                    // Okay to unwrap because every runnable task has a remaining-time entry
+                    let remaining = *self.remaining.get(&task.id).unwrap();
+                    running = Some((level, task, remaining, 0));
+                }
+            }
+        }
+
+        // TC: O(n*log(n))
+        events.sort_by_key(|event| event.time);
+        events
+    }
+}
+
+/// A contiguous run of same-`kind` tasks executed back-to-back as a unit.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Batch {
+    pub task_ids: Vec<u64>,
+    pub start_time: u32,
+    pub end_time: u32,
+}
+
+/// Wraps the existing SJF selection with a batching layer, modeled on MeiliSearch's scheduler:
+/// whenever the CPU goes idle, the shortest currently-queued task is picked to anchor a batch
+/// (composing with the existing SJF selection), and every other currently-queued task of the
+/// same `kind` is greedily folded into that same batch, so same-type work amortizes its setup
+/// cost by running contiguously.
+pub struct BatchingScheduler<'a> {
+    pub current_time: u32,
+    // Tasks that have not yet been queued, sorted reverse-chronologically for easy popping
+    unqueued_tasks: Vec<&'a Task>,
+    // Tasks that are queued and not currently running, keyed by ascending execution_duration
+    ready_queue: BinaryHeap<TaskDurationDesc<'a>>,
+}
+
+impl<'a> BatchingScheduler<'a> {
+    /// Time complexity: O(1)
+    fn unfinished(&self) -> bool {
+        !self.unqueued_tasks.is_empty() || !self.ready_queue.is_empty()
+    }
+
+    /// Queue every unqueued task whose `queued_at` has arrived by `self.current_time`.
+    /// Time complexity: O(k*log(n)) where k is the number of tasks newly queued
+    fn queue_arrivals(&mut self) {
+        while let Some(&task) = self.unqueued_tasks.last() {
+            if task.queued_at <= self.current_time {
+                self.unqueued_tasks.pop();
+                self.ready_queue.push(TaskDurationDesc(task));
+            } else {
+                break;
+            }
+        }
+    }
+
+    /// Runs the full workload, returning the batches in the order they executed.
+    /// Time complexity: O(n^2*log(n)) worst case (one coalescing scan per batch)
+    pub fn batches(&mut self) -> Vec<Batch> {
+        let mut batches = Vec::new();
+
+        while self.unfinished() {
+            // Fast-forward if nothing is runnable yet
+            if self.ready_queue.is_empty() {
+                // Okay to unwrap because unfinished() guarantees an unqueued task here
+                let next_arrival = *self.unqueued_tasks.last().unwrap();
+                self.current_time = self.current_time.max(next_arrival.queued_at);
+                self.queue_arrivals();
+            }
+
+            // The shortest currently-queued task anchors the batch (the existing SJF selection)
+            // Okay to unwrap because ready_queue is non-empty at this point
+            let TaskDurationDesc(anchor) = self.ready_queue.pop().unwrap();
+            let start_time = self.current_time;
+            let mut task_ids = vec![anchor.id];
+            let mut batch_duration = anchor.execution_duration;
+
+            // Greedily coalesce every other currently-queued task of the same kind
+            let mut leftover = Vec::new();
+            while let Some(TaskDurationDesc(task)) = self.ready_queue.pop() {
+                if task.kind == anchor.kind {
+                    task_ids.push(task.id);
+                    batch_duration += task.execution_duration;
+                } else {
+                    leftover.push(task);
+                }
+            }
+            for task in leftover {
+                self.ready_queue.push(TaskDurationDesc(task));
+            }
+
+            self.current_time += batch_duration;
+
+            // Queue any tasks that arrived while the batch was running
+            self.queue_arrivals();
+
+            batches.push(Batch { task_ids, start_time, end_time: self.current_time });
+        }
+
+        batches
+    }
+}
+
+impl<'a> Scheduler<'a> for BatchingScheduler<'a> {
+    /// Time complexity: O(n*log(n))
+    fn new(tasks: &'a [Task]) -> Self {
+        // TC: O(n)
+        let mut unqueued_tasks: Vec<&Task> = tasks.iter().collect();
+        // Sort unqueued tasks in reverse-chronological queue time for easy popping
+        // TC: O(n*log(n))
+        unqueued_tasks.sort_unstable_by(|&a, &b| b.queued_at.partial_cmp(&a.queued_at).unwrap());
+
+        Self {
+            current_time: 0,
+            unqueued_tasks,
+            ready_queue: BinaryHeap::new(),
+        }
+    }
+
+    /// The batched execution order, flattened; call `batches` directly for batch boundaries.
+    /// Time complexity: O(n^2*log(n))
+    fn execution_order(&mut self) -> Vec<u64> {
+        self.batches().into_iter().flat_map(|batch| batch.task_ids).collect()
+    }
+
+    /// Within a batch, tasks still run one at a time; this reports each one's own Started/
+    /// Finished pair in the order `batches()` would report its `task_ids`.
+    /// Time complexity: O(n^2*log(n)), same shape as `batches`
+    fn timeline(&mut self) -> Vec<ScheduleEvent> {
+        let mut events = Vec::new();
+
+        while self.unfinished() {
+            // Fast-forward if nothing is runnable yet
+            if self.ready_queue.is_empty() {
+                // Okay to unwrap because unfinished() guarantees an unqueued task here
+                let next_arrival = *self.unqueued_tasks.last().unwrap();
+                self.current_time = self.current_time.max(next_arrival.queued_at);
+            }
+
+            // Queue anything that's arrived by now, recording when each one was queued
+            while let Some(&task) = self.unqueued_tasks.last() {
+                if task.queued_at <= self.current_time {
+                    self.unqueued_tasks.pop();
+                    events.push(ScheduleEvent { time: task.queued_at, task_id: task.id, kind: EventKind::Queued });
+                    self.ready_queue.push(TaskDurationDesc(task));
+                } else {
+                    break;
+                }
+            }
+
+            // The shortest currently-queued task anchors the batch (the existing SJF selection)
+            // Okay to unwrap because ready_queue is non-empty at this point
+            let TaskDurationDesc(anchor) = self.ready_queue.pop().unwrap();
+            let mut batch_tasks = vec![anchor];
+
+            // Greedily coalesce every other currently-queued task of the same kind
+            let mut leftover = Vec::new();
+            while let Some(TaskDurationDesc(task)) = self.ready_queue.pop() {
+                if task.kind == anchor.kind {
+                    batch_tasks.push(task);
+                } else {
+                    leftover.push(task);
+                }
+            }
+            for task in leftover {
+                self.ready_queue.push(TaskDurationDesc(task));
+            }
+
+            // Run each task in the batch back-to-back, each with its own start/finish
+            for task in batch_tasks {
+                events.push(ScheduleEvent { time: self.current_time, task_id: task.id, kind: EventKind::Started });
+                self.current_time += task.execution_duration;
+                events.push(ScheduleEvent { time: self.current_time, task_id: task.id, kind: EventKind::Finished });
+            }
+
+            // Queue any tasks that arrived while the batch was running
+            while let Some(&task) = self.unqueued_tasks.last() {
+                if task.queued_at <= self.current_time {
+                    self.unqueued_tasks.pop();
+                    events.push(ScheduleEvent { time: task.queued_at, task_id: task.id, kind: EventKind::Queued });
+                    self.ready_queue.push(TaskDurationDesc(task));
+                } else {
+                    break;
+                }
+            }
+        }
+
+        // TC: O(n*log(n))
+        events.sort_by_key(|event| event.time);
+        events
+    }
+}
+
+/// Returned by `DagScheduler::execution_order`/`timeline`/`metrics` when the dependency graph
+/// can't be fully resolved - either a genuine cycle, or a task depending on an id that never
+/// finishes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CycleError {
+    // A task id known to be stuck with unresolved dependencies
+    pub task_id: u64,
+}
+
+/// Dependency-aware scheduler over a task DAG, borrowing the subtask-tree idea from the mostr
+/// task manager: a task only becomes runnable once every id in its `depends_on` has finished
+/// (in addition to its own `queued_at` having arrived). Among runnable tasks, the existing
+/// shortest-duration `BinaryHeap` selection picks what runs next.
+pub struct DagScheduler<'a> {
+    pub current_time: u32,
+    total_tasks: usize,
+    finished_count: usize,
+    // Number of not-yet-finished dependencies remaining for each task
+    in_degree: HashMap<u64, usize>,
+    // Maps a task id to the ids of tasks that depend on it
+    dependents: HashMap<u64, Vec<u64>>,
+    tasks_by_id: HashMap<u64, &'a Task>,
+    // Dependency-satisfied tasks whose queued_at hasn't arrived yet
+    dep_ready_pending_time: Vec<&'a Task>,
+    // Dependency-satisfied tasks whose queued_at has arrived, keyed by ascending duration
+    ready_heap: BinaryHeap<TaskDurationDesc<'a>>,
+}
+
+impl<'a> DagScheduler<'a> {
+    /// Time complexity: O(n)
+    pub fn new(tasks: &'a [Task]) -> Self {
+        let tasks_by_id: HashMap<u64, &Task> = tasks.iter().map(|task| (task.id, task)).collect();
+        let mut in_degree = HashMap::with_capacity(tasks.len());
+        let mut dependents: HashMap<u64, Vec<u64>> = HashMap::new();
+
+        // TC: O(n)
+        for task in tasks {
+            in_degree.insert(task.id, task.depends_on.len());
+            for &dep_id in &task.depends_on {
+                dependents.entry(dep_id).or_default().push(task.id);
+            }
+        }
+
+        let mut scheduler = Self {
+            current_time: 0,
+            total_tasks: tasks.len(),
+            finished_count: 0,
+            in_degree,
+            dependents,
+            tasks_by_id,
+            dep_ready_pending_time: Vec::new(),
+            ready_heap: BinaryHeap::new(),
+        };
+
+        // TC: O(n)
+        for task in tasks {
+            if scheduler.in_degree[&task.id] == 0 {
+                scheduler.mark_dependency_ready(task);
+            }
+        }
+
+        scheduler
+    }
+
+    /// Time complexity: O(1)
+    fn unfinished(&self) -> bool {
+        self.finished_count < self.total_tasks
+    }
+
+    /// Files a dependency-satisfied task under whichever gate it's still waiting on.
+    /// Time complexity: O(1)
+    fn mark_dependency_ready(&mut self, task: &'a Task) {
+        if task.queued_at <= self.current_time {
+            self.ready_heap.push(TaskDurationDesc(task));
+        } else {
+            self.dep_ready_pending_time.push(task);
+        }
+    }
+
+    /// Promotes every dependency-satisfied task whose `queued_at` has now arrived.
+    /// Time complexity: O(k*log(n)) where k is the number of tasks newly queued
+    fn queue_arrivals(&mut self) {
+        let current_time = self.current_time;
+        let (arrived, still_waiting): (Vec<_>, Vec<_>) = self.dep_ready_pending_time
+            .drain(..)
+            .partition(|task| task.queued_at <= current_time);
+        self.dep_ready_pending_time = still_waiting;
+        for task in arrived {
+            self.ready_heap.push(TaskDurationDesc(task));
+        }
+    }
+
+    // Time Complexity: O(n^2*log(n)) worst case
+    pub fn execution_order(&mut self) -> Result<Vec<u64>, CycleError> {
+        let mut executed_ids = Vec::new();
+
+        while self.unfinished() {
+            if self.ready_heap.is_empty() {
+                self.queue_arrivals();
+            }
+
+            if self.ready_heap.is_empty() {
+                match self.dep_ready_pending_time.iter().min_by_key(|task| task.queued_at) {
+                    Some(next) => {
+                        // Nothing runnable yet, but something will be once its time arrives
+                        self.current_time = self.current_time.max(next.queued_at);
+                        self.queue_arrivals();
+                    }
+                    None => {
+                        // Nothing runnable and nothing waiting on time either: some task's
+                        // dependencies can never be satisfied - a cycle (or a dangling id).
+                        // Okay to unwrap: unfinished() guarantees an unresolved task remains
+                        let (&task_id, _) = self.in_degree.iter().find(|&(_, &deg)| deg > 0).unwrap();
+                        return Err(CycleError { task_id });
+                    }
+                }
+            }
+
+            // Okay to unwrap because ready_heap is non-empty at this point
+            let TaskDurationDesc(task) = self.ready_heap.pop().unwrap();
+            self.current_time += task.execution_duration;
+            executed_ids.push(task.id);
+            self.finished_count += 1;
+            self.in_degree.remove(&task.id);
+
+            if let Some(dependent_ids) = self.dependents.get(&task.id).cloned() {
+                for dependent_id in dependent_ids {
+                    // Okay to unwrap: every id in `dependents` corresponds to a real task
+                    let degree = self.in_degree.get_mut(&dependent_id).unwrap();
+                    *degree -= 1;
+                    if *degree == 0 {
+                        let dependent_task = self.tasks_by_id[&dependent_id];
+                        self.mark_dependency_ready(dependent_task);
+                    }
+                }
+            }
+        }
+
+        Ok(executed_ids)
+    }
+
+    /// A structured log of every queue/start/finish transition, in the shape of
+    /// `Scheduler::timeline`. A task is considered queued the moment both of its gates open -
+    /// its dependencies have finished and its own `queued_at` has arrived - which may be well
+    /// after either one individually.
+    // Time Complexity: O(n^2*log(n)) worst case, same shape as execution_order
+    pub fn timeline(&mut self) -> Result<Vec<ScheduleEvent>, CycleError> {
+        let mut events = Vec::new();
+
+        // `new` already marked every dependency-free task ready without logging it; credit them
+        // here, at the time their only remaining gate (queued_at) opens.
+        for task in self.tasks_by_id.values() {
+            if self.in_degree[&task.id] == 0 {
+                events.push(ScheduleEvent { time: task.queued_at, task_id: task.id, kind: EventKind::Queued });
+            }
+        }
+
+        while self.unfinished() {
+            if self.ready_heap.is_empty() {
+                self.queue_arrivals();
+            }
+
+            if self.ready_heap.is_empty() {
+                match self.dep_ready_pending_time.iter().min_by_key(|task| task.queued_at) {
+                    Some(next) => {
+                        // Nothing runnable yet, but something will be once its time arrives
+                        self.current_time = self.current_time.max(next.queued_at);
+                        self.queue_arrivals();
+                    }
+                    None => {
+                        // Nothing runnable and nothing waiting on time either: some task's
+                        // dependencies can never be satisfied - a cycle (or a dangling id).
+                        // Okay to unwrap: unfinished() guarantees an unresolved task remains
+                        let (&task_id, _) = self.in_degree.iter().find(|&(_, &deg)| deg > 0).unwrap();
+                        return Err(CycleError { task_id });
+                    }
+                }
+            }
+
+            // Okay to unwrap because ready_heap is non-empty at this point
+            let TaskDurationDesc(task) = self.ready_heap.pop().unwrap();
+            events.push(ScheduleEvent { time: self.current_time, task_id: task.id, kind: EventKind::Started });
+            self.current_time += task.execution_duration;
+            events.push(ScheduleEvent { time: self.current_time, task_id: task.id, kind: EventKind::Finished });
+            self.finished_count += 1;
+            self.in_degree.remove(&task.id);
+
+            if let Some(dependent_ids) = self.dependents.get(&task.id).cloned() {
+                for dependent_id in dependent_ids {
+                    // Okay to unwrap: every id in `dependents` corresponds to a real task
+                    let degree = self.in_degree.get_mut(&dependent_id).unwrap();
+                    *degree -= 1;
+                    if *degree == 0 {
+                        let dependent_task = self.tasks_by_id[&dependent_id];
+                        // Both gates are open as of right now: the last dependency just
+                        // finished, and mark_dependency_ready only gets called once per task.
+                        let ready_time = self.current_time.max(dependent_task.queued_at);
+                        events.push(ScheduleEvent { time: ready_time, task_id: dependent_id, kind: EventKind::Queued });
+                        self.mark_dependency_ready(dependent_task);
+                    }
+                }
+            }
+        }
+
+        // TC: O(n*log(n))
+        events.sort_by_key(|event| event.time);
+        Ok(events)
+    }
+
+    /// Per-task and aggregate scheduling statistics, in the shape of `Scheduler::metrics`.
+    /// Time complexity: same as `timeline`
+    pub fn metrics(&mut self) -> Result<SchedulerMetrics, CycleError> {
+        self.timeline().map(metrics_from_timeline)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reverse_queue_order() {
+        let tasks = vec![
+            Task { id: 42, queued_at: 5, execution_duration: 3, nice: 0, priority: 0, kind: 0, depends_on: vec![] },
+            Task { id: 43, queued_at: 2, execution_duration: 3, nice: 0, priority: 0, kind: 0, depends_on: vec![] },
+            Task { id: 44, queued_at: 0, execution_duration: 2, nice: 0, priority: 0, kind: 0, depends_on: vec![] },
+        ];
+
+        let mut naive_scheduler = NaiveScheduler::new(&tasks);
+        let mut clever_scheduler = CleverScheduler::new(&tasks);
+
+        assert_eq!(naive_scheduler.execution_order(), vec![44, 43, 42]);
+        assert_eq!(clever_scheduler.execution_order(), vec![44, 43, 42]);
+    }
+
+    #[test]
+    fn accepts_slice_arg() {
+        let tasks = vec![
+            Task { id: 42, queued_at: 5, execution_duration: 3, nice: 0, priority: 0, kind: 0, depends_on: vec![] },
+            Task { id: 43, queued_at: 2, execution_duration: 3, nice: 0, priority: 0, kind: 0, depends_on: vec![] },
+            Task { id: 44, queued_at: 0, execution_duration: 2, nice: 0, priority: 0, kind: 0, depends_on: vec![] },
+        ];
+
+        let mut naive_scheduler = NaiveScheduler::new(tasks.as_slice());
+        let mut clever_scheduler = CleverScheduler::new(&tasks);
+
+        assert_eq!(naive_scheduler.execution_order(), vec![44, 43, 42]);
+        assert_eq!(clever_scheduler.execution_order(), vec![44, 43, 42]);
+    }
+
+
+    // TODO: if two tasks are available with same duration, take the one queued first
+
+    #[test]
+    fn two_items_queued_at_once() {
+        // 0: #42 is queued
+        // 0: #42 is started
+        // 1: #43 is queued
+        // 2: #44 is queued
+        // 3: #42 is finished
+        // 3: #44 is started (it is queued and has a lower execution_duration than #43)
+        // 5: #44 is finished
+        // 5: #43 is started
+        // 8: #43 is finished
+
+        let tasks = vec![
+            Task { id: 42, queued_at: 0, execution_duration: 3, nice: 0, priority: 0, kind: 0, depends_on: vec![] },
+            Task { id: 43, queued_at: 1, execution_duration: 3, nice: 0, priority: 0, kind: 0, depends_on: vec![] },
+            Task { id: 44, queued_at: 2, execution_duration: 2, nice: 0, priority: 0, kind: 0, depends_on: vec![] },
+        ];
+
+        let mut naive_scheduler = NaiveScheduler::new(&tasks);
+        let mut clever_scheduler = CleverScheduler::new(&tasks);
+
+        assert_eq!(naive_scheduler.execution_order(), vec![42, 44, 43]);
+        assert_eq!(clever_scheduler.execution_order(), vec![42, 44, 43]);
+    }
+
+    #[test]
+    fn preemptive_non_preempting_matches_sjf() {
+        // With no arrivals during execution, the preemptive scheduler should behave like SJF.
+        let tasks = vec![
+            Task { id: 42, queued_at: 5, execution_duration: 3, nice: 0, priority: 0, kind: 0, depends_on: vec![] },
+            Task { id: 43, queued_at: 2, execution_duration: 3, nice: 0, priority: 0, kind: 0, depends_on: vec![] },
+            Task { id: 44, queued_at: 0, execution_duration: 2, nice: 0, priority: 0, kind: 0, depends_on: vec![] },
+        ];
+
+        let mut scheduler = PreemptiveScheduler::new(&tasks);
+        assert_eq!(scheduler.execution_order(), vec![44, 43, 42]);
+    }
+
+    #[test]
+    fn long_task_preempted_by_shorter_arrival() {
+        // 0: #1 queued and started (remaining 10)
+        // 3: #2 (duration 2) arrives; preempts #1 (remaining drops to 7)
+        // 5: #2 finishes; #1 resumes
+        // 12: #1 finishes
+        let tasks = vec![
+            Task { id: 1, queued_at: 0, execution_duration: 10, nice: 0, priority: 0, kind: 0, depends_on: vec![] },
+            Task { id: 2, queued_at: 3, execution_duration: 2, nice: 0, priority: 0, kind: 0, depends_on: vec![] },
+        ];
+
+        let mut scheduler = PreemptiveScheduler::new(&tasks);
+        assert_eq!(scheduler.execution_order(), vec![2, 1]);
+        assert_eq!(scheduler.current_time, 12);
+    }
+
+    #[test]
+    fn chain_of_preemptions() {
+        // Each arrival is shorter than whatever is running, so it preempts: #2 preempts #1,
+        // then #3 preempts #2.
+        let tasks = vec![
+            Task { id: 1, queued_at: 0, execution_duration: 20, nice: 0, priority: 0, kind: 0, depends_on: vec![] },
+            Task { id: 2, queued_at: 2, execution_duration: 5, nice: 0, priority: 0, kind: 0, depends_on: vec![] },
+            Task { id: 3, queued_at: 4, execution_duration: 1, nice: 0, priority: 0, kind: 0, depends_on: vec![] },
+        ];
+
+        let mut scheduler = PreemptiveScheduler::new(&tasks);
+        // #3 arrives at t=4 with remaining=1, less than #2's remaining (5-2=3), so it preempts.
+        // #3 finishes at t=5, then #2 resumes (remaining 3) and finishes at t=8,
+        // then #1 resumes (remaining 18) and finishes at t=26.
+        assert_eq!(scheduler.execution_order(), vec![3, 2, 1]);
+        assert_eq!(scheduler.current_time, 26);
+    }
+
+    #[test]
+    fn fair_scheduler_alternates_equal_weight_tasks() {
+        // Two tasks with equal weight (nice 0) arriving together should trade the CPU back
+        // and forth in lockstep, ties broken by id.
+        let tasks = vec![
+            Task { id: 1, queued_at: 0, execution_duration: 6, nice: 0, priority: 0, kind: 0, depends_on: vec![] },
+            Task { id: 2, queued_at: 0, execution_duration: 6, nice: 0, priority: 0, kind: 0, depends_on: vec![] },
+        ];
+
+        let mut scheduler = FairScheduler::with_quantum(&tasks, 2);
+        assert_eq!(scheduler.execution_order(), vec![1, 2, 1, 2, 1, 2]);
+        assert_eq!(scheduler.current_time, 12);
+    }
+
+    #[test]
+    fn fair_scheduler_clamps_out_of_range_nice() {
+        // nice: -21 is out of the documented [-20, 19] range; it should clamp to -20's
+        // weight rather than panicking on an out-of-bounds table index.
+        let tasks = vec![
+            Task { id: 1, queued_at: 0, execution_duration: 4, nice: -21, priority: 0, kind: 0, depends_on: vec![] },
+        ];
+
+        let mut scheduler = FairScheduler::with_quantum(&tasks, 2);
+        assert_eq!(scheduler.execution_order(), vec![1, 1]);
+    }
+
+    #[test]
+    #[should_panic(expected = "quantum must be greater than 0")]
+    fn fair_scheduler_rejects_zero_quantum() {
+        let tasks = vec![
+            Task { id: 1, queued_at: 0, execution_duration: 1, nice: 0, priority: 0, kind: 0, depends_on: vec![] },
+        ];
+
+        FairScheduler::with_quantum(&tasks, 0);
+    }
+
+    #[test]
+    fn fair_scheduler_favors_higher_weight() {
+        // Both tasks do the same total amount of work, so they get the same number of slices
+        // either way - but the lower-nice (higher-weight) task should be front-loaded, racking
+        // up its share of slices sooner and finishing first in wall-clock time.
+        let tasks = vec![
+            Task { id: 1, queued_at: 0, execution_duration: 1000, nice: -4, priority: 0, kind: 0, depends_on: vec![] },
+            Task { id: 2, queued_at: 0, execution_duration: 1000, nice: 0, priority: 0, kind: 0, depends_on: vec![] },
+        ];
+
+        let mut scheduler = FairScheduler::with_quantum(&tasks, 10);
+        let order = scheduler.execution_order();
+
+        let last_slice_1 = order.iter().rposition(|&id| id == 1).unwrap();
+        let last_slice_2 = order.iter().rposition(|&id| id == 2).unwrap();
+        assert!(last_slice_1 < last_slice_2);
+    }
+
+    #[test]
+    fn real_time_scheduler_runs_highest_priority_first() {
+        // A higher-priority task should run to completion before a lower-priority one, even
+        // though it takes longer.
+        let tasks = vec![
+            Task { id: 1, queued_at: 0, execution_duration: 10, nice: 0, priority: 5, kind: 0, depends_on: vec![] },
+            Task { id: 2, queued_at: 0, execution_duration: 5, nice: 0, priority: 1, kind: 0, depends_on: vec![] },
+        ];
+
+        let mut scheduler = RealTimeScheduler::new(&tasks);
+        assert_eq!(scheduler.execution_order(), vec![1, 2]);
+        assert_eq!(scheduler.current_time, 15);
+    }
+
+    #[test]
+    #[should_panic(expected = "time_slice must be greater than 0")]
+    fn real_time_scheduler_rejects_zero_time_slice() {
+        let tasks = vec![
+            Task { id: 1, queued_at: 0, execution_duration: 1, nice: 0, priority: 0, kind: 0, depends_on: vec![] },
+        ];
+
+        RealTimeScheduler::with_time_slice(&tasks, Some(0));
+    }
+
+    #[test]
+    fn real_time_scheduler_round_robins_within_a_level() {
+        // Two equal-priority tasks sharing a time-slice should interleave round-robin style.
+        let tasks = vec![
+            Task { id: 1, queued_at: 0, execution_duration: 9, nice: 0, priority: 3, kind: 0, depends_on: vec![] },
+            Task { id: 2, queued_at: 1, execution_duration: 6, nice: 0, priority: 3, kind: 0, depends_on: vec![] },
+        ];
+
+        let mut scheduler = RealTimeScheduler::with_time_slice(&tasks, Some(3));
+        assert_eq!(scheduler.execution_order(), vec![2, 1]);
+        assert_eq!(scheduler.current_time, 15);
+    }
+
+    #[test]
+    fn real_time_scheduler_preempts_on_higher_priority_arrival() {
+        // #1 (priority 1) starts running at t=0. #2 (priority 10) arrives at t=50 and, being
+        // higher-priority, should cut #1's run short immediately rather than waiting for #1 to
+        // finish or for its own next dispatch point.
+        let tasks = vec![
+            Task { id: 1, queued_at: 0, execution_duration: 100, nice: 0, priority: 1, kind: 0, depends_on: vec![] },
+            Task { id: 2, queued_at: 50, execution_duration: 5, nice: 0, priority: 10, kind: 0, depends_on: vec![] },
+        ];
+
+        let mut scheduler = RealTimeScheduler::new(&tasks);
+        assert_eq!(scheduler.execution_order(), vec![2, 1]);
+        assert_eq!(scheduler.current_time, 105);
+    }
+
+    #[test]
+    fn real_time_scheduler_does_not_preempt_for_equal_or_lower_priority() {
+        // An equal- or lower-priority arrival must not interrupt the running task; it just
+        // waits its turn behind it.
+        let tasks = vec![
+            Task { id: 1, queued_at: 0, execution_duration: 10, nice: 0, priority: 5, kind: 0, depends_on: vec![] },
+            Task { id: 2, queued_at: 3, execution_duration: 2, nice: 0, priority: 5, kind: 0, depends_on: vec![] },
+            Task { id: 3, queued_at: 4, execution_duration: 1, nice: 0, priority: 1, kind: 0, depends_on: vec![] },
+        ];
+
+        let mut scheduler = RealTimeScheduler::new(&tasks);
+        assert_eq!(scheduler.execution_order(), vec![1, 2, 3]);
+        assert_eq!(scheduler.current_time, 13);
+    }
+
+    #[test]
+    fn timeline_matches_the_documented_event_log() {
+        // 0: #42 queued, 0: #42 started
+        // 1: #43 queued
+        // 2: #44 queued
+        // 3: #42 finished, 3: #44 started
+        // 5: #44 finished, 5: #43 started
+        // 8: #43 finished
+        let tasks = vec![
+            Task { id: 42, queued_at: 0, execution_duration: 3, nice: 0, priority: 0, kind: 0, depends_on: vec![] },
+            Task { id: 43, queued_at: 1, execution_duration: 3, nice: 0, priority: 0, kind: 0, depends_on: vec![] },
+            Task { id: 44, queued_at: 2, execution_duration: 2, nice: 0, priority: 0, kind: 0, depends_on: vec![] },
+        ];
+
+        let expected = vec![
+            ScheduleEvent { time: 0, task_id: 42, kind: EventKind::Queued },
+            ScheduleEvent { time: 0, task_id: 42, kind: EventKind::Started },
+            ScheduleEvent { time: 1, task_id: 43, kind: EventKind::Queued },
+            ScheduleEvent { time: 2, task_id: 44, kind: EventKind::Queued },
+            ScheduleEvent { time: 3, task_id: 42, kind: EventKind::Finished },
+            ScheduleEvent { time: 3, task_id: 44, kind: EventKind::Started },
+            ScheduleEvent { time: 5, task_id: 44, kind: EventKind::Finished },
+            ScheduleEvent { time: 5, task_id: 43, kind: EventKind::Started },
+            ScheduleEvent { time: 8, task_id: 43, kind: EventKind::Finished },
+        ];
+
+        let mut naive_scheduler = NaiveScheduler::new(&tasks);
+        let mut clever_scheduler = CleverScheduler::new(&tasks);
+
+        assert_eq!(naive_scheduler.timeline(), expected);
+        assert_eq!(clever_scheduler.timeline(), expected);
+    }
+
+    #[test]
+    fn metrics_computed_from_the_timeline() {
+        let tasks = vec![
+            Task { id: 42, queued_at: 0, execution_duration: 3, nice: 0, priority: 0, kind: 0, depends_on: vec![] },
+            Task { id: 43, queued_at: 1, execution_duration: 3, nice: 0, priority: 0, kind: 0, depends_on: vec![] },
+            Task { id: 44, queued_at: 2, execution_duration: 2, nice: 0, priority: 0, kind: 0, depends_on: vec![] },
+        ];
+
+        let expected_per_task = vec![
+            TaskMetrics { task_id: 42, turnaround: 3, waiting: 0, response: 0 },
+            TaskMetrics { task_id: 43, turnaround: 7, waiting: 4, response: 4 },
+            TaskMetrics { task_id: 44, turnaround: 3, waiting: 1, response: 1 },
+        ];
+
+        let mut naive_scheduler = NaiveScheduler::new(&tasks);
+        let naive_metrics = naive_scheduler.metrics();
+
+        assert_eq!(naive_metrics.per_task, expected_per_task);
+        assert_eq!(naive_metrics.makespan, 8);
+        assert!((naive_metrics.avg_turnaround - 13.0 / 3.0).abs() < 1e-9);
+        assert!((naive_metrics.avg_waiting - 5.0 / 3.0).abs() < 1e-9);
+        assert!((naive_metrics.avg_response - 5.0 / 3.0).abs() < 1e-9);
+
+        let mut clever_scheduler = CleverScheduler::new(&tasks);
+        assert_eq!(clever_scheduler.metrics(), naive_metrics);
+    }
+
+    #[test]
+    fn metrics_on_an_empty_task_set_has_no_nan_averages() {
+        let tasks: Vec<Task> = vec![];
+
+        let mut scheduler = NaiveScheduler::new(&tasks);
+        let metrics = scheduler.metrics();
+
+        assert!(metrics.per_task.is_empty());
+        assert_eq!(metrics.makespan, 0);
+        assert_eq!(metrics.avg_turnaround, 0.0);
+        assert_eq!(metrics.avg_waiting, 0.0);
+        assert_eq!(metrics.avg_response, 0.0);
+    }
+
+    #[test]
+    fn batching_scheduler_coalesces_same_kind_tasks() {
+        // #2 is the shortest task and anchors the first batch; #1 shares its kind and gets
+        // folded in, but #3 is a different kind and has to wait for its own batch.
+        let tasks = vec![
+            Task { id: 1, queued_at: 0, execution_duration: 3, nice: 0, priority: 0, kind: 1, depends_on: vec![] },
+            Task { id: 2, queued_at: 0, execution_duration: 2, nice: 0, priority: 0, kind: 1, depends_on: vec![] },
+            Task { id: 3, queued_at: 0, execution_duration: 5, nice: 0, priority: 0, kind: 2, depends_on: vec![] },
+        ];
+
+        let mut scheduler = BatchingScheduler::new(&tasks);
+        let batches = scheduler.batches();
+
+        assert_eq!(batches, vec![
+            Batch { task_ids: vec![2, 1], start_time: 0, end_time: 5 },
+            Batch { task_ids: vec![3], start_time: 5, end_time: 10 },
+        ]);
+
+        let mut flattened_scheduler = BatchingScheduler::new(&tasks);
+        assert_eq!(flattened_scheduler.execution_order(), vec![2, 1, 3]);
+    }
+
+    #[test]
+    fn dag_scheduler_runs_dependencies_before_dependents() {
+        // #1 unblocks both #2 and #3; #4 waits on both of those before it can run.
+        let tasks = vec![
+            Task { id: 1, queued_at: 0, execution_duration: 2, nice: 0, priority: 0, kind: 0, depends_on: vec![] },
+            Task { id: 2, queued_at: 0, execution_duration: 3, nice: 0, priority: 0, kind: 0, depends_on: vec![1] },
+            Task { id: 3, queued_at: 0, execution_duration: 1, nice: 0, priority: 0, kind: 0, depends_on: vec![1] },
+            Task { id: 4, queued_at: 0, execution_duration: 1, nice: 0, priority: 0, kind: 0, depends_on: vec![2, 3] },
+        ];
+
+        let mut scheduler = DagScheduler::new(&tasks);
+        assert_eq!(scheduler.execution_order(), Ok(vec![1, 3, 2, 4]));
+        assert_eq!(scheduler.current_time, 7);
+    }
+
+    #[test]
+    fn dag_scheduler_reports_a_cycle() {
+        let tasks = vec![
+            Task { id: 1, queued_at: 0, execution_duration: 1, nice: 0, priority: 0, kind: 0, depends_on: vec![2] },
+            Task { id: 2, queued_at: 0, execution_duration: 1, nice: 0, priority: 0, kind: 0, depends_on: vec![1] },
+        ];
+
+        let mut scheduler = DagScheduler::new(&tasks);
+        let result = scheduler.execution_order();
+
+        let error = result.expect_err("a two-task mutual dependency is a cycle");
+        assert!(error.task_id == 1 || error.task_id == 2);
+    }
+
+    #[test]
+    fn dag_scheduler_metrics_built_from_its_own_timeline() {
+        // Same DAG as dag_scheduler_runs_dependencies_before_dependents: #1 unblocks both #2
+        // and #3; #4 waits on both. #2 and #3 aren't queued (dependency-satisfied) until #1
+        // finishes at t=2, even though every task's own queued_at is 0.
+        let tasks = vec![
+            Task { id: 1, queued_at: 0, execution_duration: 2, nice: 0, priority: 0, kind: 0, depends_on: vec![] },
+            Task { id: 2, queued_at: 0, execution_duration: 3, nice: 0, priority: 0, kind: 0, depends_on: vec![1] },
+            Task { id: 3, queued_at: 0, execution_duration: 1, nice: 0, priority: 0, kind: 0, depends_on: vec![1] },
+            Task { id: 4, queued_at: 0, execution_duration: 1, nice: 0, priority: 0, kind: 0, depends_on: vec![2, 3] },
+        ];
+
+        let expected_per_task = vec![
+            TaskMetrics { task_id: 1, turnaround: 2, waiting: 0, response: 0 },
+            TaskMetrics { task_id: 2, turnaround: 4, waiting: 1, response: 1 },
+            TaskMetrics { task_id: 3, turnaround: 1, waiting: 0, response: 0 },
+            TaskMetrics { task_id: 4, turnaround: 1, waiting: 0, response: 0 },
+        ];
+
+        let mut scheduler = DagScheduler::new(&tasks);
+        let metrics = scheduler.metrics().expect("this DAG has no cycle");
+
+        assert_eq!(metrics.per_task, expected_per_task);
+        assert_eq!(metrics.makespan, 7);
+        assert!((metrics.avg_turnaround - 2.0).abs() < 1e-9);
+        assert!((metrics.avg_waiting - 0.25).abs() < 1e-9);
+        assert!((metrics.avg_response - 0.25).abs() < 1e-9);
+    }
+
+    #[test]
+    fn dag_scheduler_metrics_reports_a_cycle() {
+        let tasks = vec![
+            Task { id: 1, queued_at: 0, execution_duration: 1, nice: 0, priority: 0, kind: 0, depends_on: vec![2] },
+            Task { id: 2, queued_at: 0, execution_duration: 1, nice: 0, priority: 0, kind: 0, depends_on: vec![1] },
+        ];
+
+        let mut scheduler = DagScheduler::new(&tasks);
+        let error = scheduler.metrics().expect_err("a two-task mutual dependency is a cycle");
+        assert!(error.task_id == 1 || error.task_id == 2);
+    }
+
+    #[test]
+    fn metrics_available_on_every_scheduling_class() {
+        // metrics() is built on timeline(), so every Scheduler impl needs its own override -
+        // this exercises the ones that don't have a more specific timeline/metrics test above.
+        let tasks = vec![
+            Task { id: 1, queued_at: 0, execution_duration: 10, nice: 0, priority: 5, kind: 1, depends_on: vec![] },
+            Task { id: 2, queued_at: 3, execution_duration: 2, nice: -4, priority: 1, kind: 1, depends_on: vec![] },
+        ];
+
+        let mut preemptive = PreemptiveScheduler::new(&tasks);
+        assert_eq!(preemptive.metrics().makespan, 12);
+
+        let mut fair = FairScheduler::with_quantum(&tasks, 2);
+        assert_eq!(fair.metrics().makespan, fair.current_time);
+
+        let mut real_time = RealTimeScheduler::with_time_slice(&tasks, Some(3));
+        assert_eq!(real_time.metrics().makespan, real_time.current_time);
+
+        let mut batching = BatchingScheduler::new(&tasks);
+        assert_eq!(batching.metrics().makespan, batching.current_time);
     }
 }